@@ -0,0 +1,96 @@
+// error.rs
+
+use std::fmt;
+use std::ops::Range;
+
+/// A byte-offset range into the original source line, as produced by the
+/// lexer's `logos::Lexer::span()`.
+pub type Span = Range<usize>;
+
+/// Unified error type for lexing, parsing, and evaluation, so every stage
+/// can report a span pointing at the offending input instead of a bare
+/// `String`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum YelraError {
+    UnexpectedChar { span: Span },
+    MalformedNumber { span: Span },
+    UnclosedParen { span: Span },
+    UnexpectedRParen { span: Span },
+    TrailingTokens { span: Span },
+    UnexpectedEof { span: Span },
+    InvalidEscape { span: Span },
+    EmptyList,
+    InvalidListHead,
+    UnboundSymbol(String),
+    UnknownOperator(String),
+    ArityMismatch { op: String, expected: usize },
+    TypeMismatch { op: String, expected: &'static str },
+    DivisionByZero,
+}
+
+impl YelraError {
+    /// The span this error points at, if it has one. Errors that aren't
+    /// tied to a specific source location (e.g. `DivisionByZero`, which is
+    /// only known once arguments are evaluated) return `None`.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            YelraError::UnexpectedChar { span }
+            | YelraError::MalformedNumber { span }
+            | YelraError::UnclosedParen { span }
+            | YelraError::UnexpectedRParen { span }
+            | YelraError::TrailingTokens { span }
+            | YelraError::UnexpectedEof { span }
+            | YelraError::InvalidEscape { span } => Some(span.clone()),
+            YelraError::EmptyList
+            | YelraError::InvalidListHead
+            | YelraError::UnboundSymbol(_)
+            | YelraError::UnknownOperator(_)
+            | YelraError::ArityMismatch { .. }
+            | YelraError::TypeMismatch { .. }
+            | YelraError::DivisionByZero => None,
+        }
+    }
+}
+
+impl fmt::Display for YelraError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            YelraError::UnexpectedChar { span } => {
+                write!(f, "unexpected character at {}..{}", span.start, span.end)
+            }
+            YelraError::MalformedNumber { span } => {
+                write!(f, "malformed number at {}..{}", span.start, span.end)
+            }
+            YelraError::UnclosedParen { span } => {
+                write!(f, "unclosed '(' opened at {}..{}", span.start, span.end)
+            }
+            YelraError::UnexpectedRParen { span } => {
+                write!(f, "unexpected ')' at {}..{}", span.start, span.end)
+            }
+            YelraError::TrailingTokens { span } => {
+                write!(f, "trailing tokens starting at {}..{}", span.start, span.end)
+            }
+            YelraError::UnexpectedEof { span } => {
+                write!(f, "unexpected end of input at {}..{}", span.start, span.end)
+            }
+            YelraError::InvalidEscape { span } => {
+                write!(f, "invalid escape sequence at {}..{}", span.start, span.end)
+            }
+            YelraError::EmptyList => write!(f, "cannot evaluate an empty list"),
+            YelraError::InvalidListHead => {
+                write!(f, "first element of list must be a symbol (operator)")
+            }
+            YelraError::UnboundSymbol(s) => write!(f, "unbound symbol '{}'", s),
+            YelraError::UnknownOperator(op) => write!(f, "unknown operator '{}'", op),
+            YelraError::ArityMismatch { op, expected } => {
+                write!(f, "'{}' expects {} argument(s)", op, expected)
+            }
+            YelraError::TypeMismatch { op, expected } => {
+                write!(f, "'{}' expects a {} argument", op, expected)
+            }
+            YelraError::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+impl std::error::Error for YelraError {}
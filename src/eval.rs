@@ -1,76 +1,538 @@
 use crate::ast::Expr;
+use crate::error::YelraError;
+use std::collections::HashMap;
 use std::fmt;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     Number(f64),
+    Bool(bool),
+    Str(String),
+    /// A boxed operator, produced by quoting it with a leading backslash
+    /// (e.g. `\+`), so it can be passed around like any other value.
+    Builtin(String),
+    List(Vec<Value>),
 }
 
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Value::Number(n) => write!(f, "{}", n),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Str(s) => write!(f, "{}", s),
+            Value::Builtin(name) => write!(f, "#<builtin {}>", name),
+            Value::List(items) => {
+                write!(f, "(")?;
+                for (i, v) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{}", v)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// The evaluation environment: a stack of scopes, innermost last. `let`
+/// with a single binding defines into the current (innermost) scope;
+/// `let` with a bindings block pushes a fresh scope for its body and pops
+/// it again afterward, so the bindings don't leak out.
+pub struct Env {
+    scopes: Vec<HashMap<String, Value>>,
+}
+
+impl Env {
+    pub fn new() -> Self {
+        Env {
+            scopes: vec![HashMap::new()],
+        }
+    }
+
+    fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    pub fn define(&mut self, name: String, value: Value) {
+        self.scopes
+            .last_mut()
+            .expect("Env always has at least one scope")
+            .insert(name, value);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+}
+
+impl Default for Env {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn as_number(v: &Value, op: &str) -> Result<f64, YelraError> {
+    match v {
+        Value::Number(n) => Ok(*n),
+        Value::Bool(_) | Value::Str(_) | Value::Builtin(_) | Value::List(_) => {
+            Err(YelraError::TypeMismatch {
+                op: op.to_string(),
+                expected: "Number",
+            })
+        }
+    }
+}
+
+fn as_bool(v: &Value, op: &str) -> Result<bool, YelraError> {
+    match v {
+        Value::Bool(b) => Ok(*b),
+        Value::Number(_) | Value::Str(_) | Value::Builtin(_) | Value::List(_) => {
+            Err(YelraError::TypeMismatch {
+                op: op.to_string(),
+                expected: "Bool",
+            })
+        }
+    }
+}
+
+fn as_str<'a>(v: &'a Value, op: &str) -> Result<&'a str, YelraError> {
+    match v {
+        Value::Str(s) => Ok(s),
+        Value::Number(_) | Value::Bool(_) | Value::Builtin(_) | Value::List(_) => {
+            Err(YelraError::TypeMismatch {
+                op: op.to_string(),
+                expected: "Str",
+            })
+        }
+    }
+}
+
+fn as_builtin<'a>(v: &'a Value, op: &str) -> Result<&'a str, YelraError> {
+    match v {
+        Value::Builtin(name) => Ok(name),
+        Value::Number(_) | Value::Bool(_) | Value::Str(_) | Value::List(_) => {
+            Err(YelraError::TypeMismatch {
+                op: op.to_string(),
+                expected: "Builtin",
+            })
+        }
+    }
+}
+
+fn as_list<'a>(v: &'a Value, op: &str) -> Result<&'a [Value], YelraError> {
+    match v {
+        Value::List(items) => Ok(items),
+        Value::Number(_) | Value::Bool(_) | Value::Str(_) | Value::Builtin(_) => {
+            Err(YelraError::TypeMismatch {
+                op: op.to_string(),
+                expected: "List",
+            })
+        }
+    }
+}
+
+/// Apply a builtin operator to already-evaluated arguments. This is the
+/// shared tail end of both call forms: `(op arg...)` where `op` is a
+/// literal symbol, and `(expr arg...)` where `expr` evaluated to a
+/// `Value::Builtin`.
+fn apply_builtin(op: &str, args: &[Value]) -> Result<Value, YelraError> {
+    match op {
+        "+" => {
+            let mut res = 0.0;
+            for v in args {
+                res += as_number(v, op)?;
+            }
+            Ok(Value::Number(res))
+        }
+        "-" => {
+            let nums = args
+                .iter()
+                .map(|v| as_number(v, op))
+                .collect::<Result<Vec<f64>, YelraError>>()?;
+            match nums.len() {
+                0 => Err(YelraError::ArityMismatch {
+                    op: op.to_string(),
+                    expected: 1,
+                }),
+                1 => Ok(Value::Number(-nums[0])),
+                _ => {
+                    let mut res = nums[0];
+                    for v in &nums[1..] {
+                        res -= v;
+                    }
+                    Ok(Value::Number(res))
+                }
+            }
+        }
+        "*" => {
+            let mut res = 1.0;
+            for v in args {
+                res *= as_number(v, op)?;
+            }
+            Ok(Value::Number(res))
+        }
+        "/" => {
+            let nums = args
+                .iter()
+                .map(|v| as_number(v, op))
+                .collect::<Result<Vec<f64>, YelraError>>()?;
+            match nums.len() {
+                0 => Err(YelraError::ArityMismatch {
+                    op: op.to_string(),
+                    expected: 1,
+                }),
+                1 => Ok(Value::Number(1.0 / nums[0])),
+                _ => {
+                    let mut res = nums[0];
+                    for v in &nums[1..] {
+                        if *v == 0.0 {
+                            return Err(YelraError::DivisionByZero);
+                        }
+                        res /= v;
+                    }
+                    Ok(Value::Number(res))
+                }
+            }
+        }
+        "^" => {
+            if args.len() != 2 {
+                return Err(YelraError::ArityMismatch {
+                    op: op.to_string(),
+                    expected: 2,
+                });
+            }
+            let base = as_number(&args[0], op)?;
+            let exp = as_number(&args[1], op)?;
+            Ok(Value::Number(base.powf(exp)))
+        }
+        "=" | "<" | ">" | "<=" | ">=" | "!=" => {
+            if args.len() != 2 {
+                return Err(YelraError::ArityMismatch {
+                    op: op.to_string(),
+                    expected: 2,
+                });
+            }
+            let a = as_number(&args[0], op)?;
+            let b = as_number(&args[1], op)?;
+            let result = match op {
+                "=" => a == b,
+                "<" => a < b,
+                ">" => a > b,
+                "<=" => a <= b,
+                ">=" => a >= b,
+                "!=" => a != b,
+                _ => unreachable!(),
+            };
+            Ok(Value::Bool(result))
+        }
+        "and" => {
+            let mut result = true;
+            for v in args {
+                result &= as_bool(v, op)?;
+            }
+            Ok(Value::Bool(result))
+        }
+        "or" => {
+            let mut result = false;
+            for v in args {
+                result |= as_bool(v, op)?;
+            }
+            Ok(Value::Bool(result))
+        }
+        "not" => {
+            if args.len() != 1 {
+                return Err(YelraError::ArityMismatch {
+                    op: op.to_string(),
+                    expected: 1,
+                });
+            }
+            Ok(Value::Bool(!as_bool(&args[0], op)?))
+        }
+        "string-append" => {
+            let mut res = String::new();
+            for v in args {
+                res.push_str(as_str(v, op)?);
+            }
+            Ok(Value::Str(res))
+        }
+        "string-length" => {
+            if args.len() != 1 {
+                return Err(YelraError::ArityMismatch {
+                    op: op.to_string(),
+                    expected: 1,
+                });
+            }
+            Ok(Value::Number(as_str(&args[0], op)?.chars().count() as f64))
+        }
+        "substring" => {
+            if args.len() != 3 {
+                return Err(YelraError::ArityMismatch {
+                    op: op.to_string(),
+                    expected: 3,
+                });
+            }
+            let s = as_str(&args[0], op)?;
+            let start = as_number(&args[1], op)? as usize;
+            let end = as_number(&args[2], op)? as usize;
+            let res = s.chars().skip(start).take(end.saturating_sub(start));
+            Ok(Value::Str(res.collect()))
+        }
+        "list" => Ok(Value::List(args.to_vec())),
+        "reduce" => {
+            if args.len() != 3 {
+                return Err(YelraError::ArityMismatch {
+                    op: op.to_string(),
+                    expected: 3,
+                });
+            }
+            let f = as_builtin(&args[0], op)?;
+            let items = as_list(&args[2], op)?;
+            let mut acc = args[1].clone();
+            for item in items {
+                acc = apply_builtin(f, &[acc, item.clone()])?;
+            }
+            Ok(acc)
+        }
+        "map" => {
+            if args.len() != 2 {
+                return Err(YelraError::ArityMismatch {
+                    op: op.to_string(),
+                    expected: 2,
+                });
+            }
+            let f = as_builtin(&args[0], op)?;
+            let items = as_list(&args[1], op)?;
+            let mapped = items
+                .iter()
+                .map(|item| apply_builtin(f, std::slice::from_ref(item)))
+                .collect::<Result<Vec<Value>, YelraError>>()?;
+            Ok(Value::List(mapped))
         }
+        other => Err(YelraError::UnknownOperator(other.to_string())),
     }
 }
 
-pub fn eval(expr: &Expr) -> Result<Value, String> {
+/// Evaluate a single `(name valueExpr)` binding pair, defining it into
+/// `env`'s current scope.
+fn eval_binding(binding: &Expr, env: &mut Env) -> Result<(), YelraError> {
+    let pair = match binding {
+        Expr::List(p) if p.len() == 2 => p,
+        _ => return Err(YelraError::InvalidListHead),
+    };
+    let name = match &pair[0] {
+        Expr::Symbol(n) => n.clone(),
+        _ => return Err(YelraError::InvalidListHead),
+    };
+    let val = eval(&pair[1], env)?;
+    env.define(name, val);
+    Ok(())
+}
+
+pub fn eval(expr: &Expr, env: &mut Env) -> Result<Value, YelraError> {
     match expr {
         Expr::Number(n) => Ok(Value::Number(*n)),
-        Expr::Symbol(s) => Err(format!("Unbound symbol '{}'", s)),
+        Expr::Str(s) => Ok(Value::Str(s.clone())),
+        Expr::Quote(op) => Ok(Value::Builtin(op.clone())),
+        // `true`/`false` are bare symbols at the lexer/parser level, but
+        // evaluate to Bool literals rather than an environment lookup —
+        // the only way to write a literal Bool, since the comparison ops
+        // are the only other source of one.
+        Expr::Symbol(s) if s == "true" => Ok(Value::Bool(true)),
+        Expr::Symbol(s) if s == "false" => Ok(Value::Bool(false)),
+        Expr::Symbol(s) => env
+            .get(s)
+            .cloned()
+            .ok_or_else(|| YelraError::UnboundSymbol(s.clone())),
         Expr::List(list) => {
             if list.is_empty() {
-                return Err("Cannot evaluate empty list".to_string());
+                return Err(YelraError::EmptyList);
             }
+
             match &list[0] {
-                Expr::Symbol(op) => {
-                    // evaluate arguments (we only support numeric values for now)
-                    let mut args = Vec::new();
-                    for a in &list[1..] {
-                        match eval(a)? {
-                            Value::Number(n) => args.push(n),
-                        }
+                // `if` is a special form: only the taken branch is evaluated,
+                // unlike every other operator below which evaluates all of
+                // its arguments eagerly.
+                Expr::Symbol(op) if op == "if" => {
+                    if list.len() != 4 {
+                        return Err(YelraError::ArityMismatch {
+                            op: "if".to_string(),
+                            expected: 3,
+                        });
                     }
-
-                    match op.as_str() {
-                        "+" => Ok(Value::Number(args.iter().sum())),
-                        "-" => {
-                            match args.len() {
-                                0 => Err("'-' needs at least one argument".to_string()),
-                                1 => Ok(Value::Number(-args[0])),
-                                _ => {
-                                    let mut res = args[0];
-                                    for v in &args[1..] { res -= v; }
-                                    Ok(Value::Number(res))
-                                }
-                            }
-                        }
-                        "*" => {
-                            let mut res = 1.0;
-                            for v in &args { res *= v; }
-                            Ok(Value::Number(res))
+                    let cond = as_bool(&eval(&list[1], env)?, "if")?;
+                    if cond {
+                        eval(&list[2], env)
+                    } else {
+                        eval(&list[3], env)
+                    }
+                }
+                // `let` is also a special form: `(let x 5)` binds into the
+                // current scope, while `(let ((x 1) (y 2)) body)` opens a
+                // child scope for its bindings and body, then discards it.
+                Expr::Symbol(op) if op == "let" => {
+                    if list.len() != 3 {
+                        return Err(YelraError::ArityMismatch {
+                            op: "let".to_string(),
+                            expected: 2,
+                        });
+                    }
+                    match &list[1] {
+                        Expr::Symbol(name) => {
+                            let val = eval(&list[2], env)?;
+                            env.define(name.clone(), val.clone());
+                            Ok(val)
                         }
-                        "/" => {
-                            match args.len() {
-                                0 => Err("'/' needs at least one argument".to_string()),
-                                1 => Ok(Value::Number(1.0 / args[0])),
-                                _ => {
-                                    let mut res = args[0];
-                                    for v in &args[1..] {
-                                        if *v == 0.0 {
-                                            return Err("division by zero".to_string());
-                                        }
-                                        res /= v;
-                                    }
-                                    Ok(Value::Number(res))
-                                }
-                            }
+                        Expr::List(bindings) => {
+                            env.push_scope();
+                            let result = bindings
+                                .iter()
+                                .try_for_each(|b| eval_binding(b, env))
+                                .and_then(|_| eval(&list[2], env));
+                            env.pop_scope();
+                            result
                         }
-                        other => Err(format!("Unknown operator '{}'", other))
+                        _ => Err(YelraError::InvalidListHead),
+                    }
+                }
+                Expr::Symbol(op) => {
+                    let mut args = Vec::with_capacity(list.len() - 1);
+                    for e in &list[1..] {
+                        args.push(eval(e, env)?);
+                    }
+                    apply_builtin(op, &args)
+                }
+                // The head isn't a literal operator symbol — it might still
+                // evaluate to a first-class `Value::Builtin` (e.g. a `\+`
+                // passed in as an argument and applied here).
+                head => {
+                    let name = match eval(head, env)? {
+                        Value::Builtin(name) => name,
+                        _ => return Err(YelraError::InvalidListHead),
+                    };
+                    let mut args = Vec::with_capacity(list.len() - 1);
+                    for e in &list[1..] {
+                        args.push(eval(e, env)?);
                     }
+                    apply_builtin(&name, &args)
                 }
-                _ => Err("First element of list must be a symbol (operator)".to_string()),
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::{tokenize, LexerOptions};
+    use crate::parser::parse;
+
+    fn eval_str(input: &str) -> Value {
+        let tokens = tokenize(input, &LexerOptions::default()).unwrap();
+        let expr = parse(tokens).unwrap();
+        eval(&expr, &mut Env::new()).unwrap()
+    }
+
+    #[test]
+    fn string_append_concatenates() {
+        assert_eq!(
+            eval_str(r#"(string-append "foo" "bar")"#),
+            Value::Str("foobar".to_string())
+        );
+    }
+
+    #[test]
+    fn string_length_counts_chars() {
+        assert_eq!(eval_str(r#"(string-length "hello")"#), Value::Number(5.0));
+    }
+
+    #[test]
+    fn substring_extracts_range() {
+        assert_eq!(
+            eval_str(r#"(substring "hello world" 6 11)"#),
+            Value::Str("world".to_string())
+        );
+    }
+
+    #[test]
+    fn let_block_scope_is_discarded_after_use() {
+        fn eval_in(input: &str, env: &mut Env) -> Value {
+            let tokens = tokenize(input, &LexerOptions::default()).unwrap();
+            let expr = parse(tokens).unwrap();
+            eval(&expr, env).unwrap()
+        }
+
+        let mut env = Env::new();
+        eval_in("(let x 1)", &mut env);
+        assert_eq!(
+            eval_in("(let ((x 2) (y 3)) (+ x y))", &mut env),
+            Value::Number(5.0)
+        );
+        // The block-form `let` bindings must not leak into the outer scope.
+        assert_eq!(eval_in("x", &mut env), Value::Number(1.0));
+    }
+
+    #[test]
+    fn if_only_evaluates_taken_branch() {
+        // The else-branch calls an unbound symbol, which would error if
+        // evaluated — `if` must skip it once the condition is true.
+        assert_eq!(eval_str("(if (= 1 1) 42 undefined-symbol)"), Value::Number(42.0));
+    }
+
+    #[test]
+    fn caret_raises_to_the_power() {
+        assert_eq!(eval_str("2 ^ 10"), Value::Number(1024.0));
+    }
+
+    #[test]
+    fn if_false_branch_is_taken_when_condition_is_false() {
+        assert_eq!(eval_str("(if false 1 2)"), Value::Number(2.0));
+    }
+
+    #[test]
+    fn comparison_ops_produce_bools() {
+        assert_eq!(eval_str("(= 1 1)"), Value::Bool(true));
+        assert_eq!(eval_str("(< 1 2)"), Value::Bool(true));
+        assert_eq!(eval_str("(> 1 2)"), Value::Bool(false));
+        assert_eq!(eval_str("(<= 2 2)"), Value::Bool(true));
+        assert_eq!(eval_str("(>= 1 2)"), Value::Bool(false));
+        assert_eq!(eval_str("(!= 1 2)"), Value::Bool(true));
+    }
+
+    #[test]
+    fn and_or_not_on_bool_literals() {
+        assert_eq!(eval_str("(and true false)"), Value::Bool(false));
+        assert_eq!(eval_str("(or true false)"), Value::Bool(true));
+        assert_eq!(eval_str("(not true)"), Value::Bool(false));
+    }
+
+    #[test]
+    fn bool_display_prints_true_and_false() {
+        assert_eq!(Value::Bool(true).to_string(), "true");
+        assert_eq!(Value::Bool(false).to_string(), "false");
+    }
+
+    #[test]
+    fn reduce_folds_with_builtin() {
+        assert_eq!(
+            eval_str(r#"(reduce \+ 0 (list 1 2 3))"#),
+            Value::Number(6.0)
+        );
+    }
+
+    #[test]
+    fn map_applies_builtin_to_each_item() {
+        assert_eq!(
+            eval_str(r#"(map \- (list 1 2 3))"#),
+            Value::List(vec![
+                Value::Number(-1.0),
+                Value::Number(-2.0),
+                Value::Number(-3.0)
+            ])
+        );
+    }
+}
@@ -2,6 +2,8 @@
 
 use logos::Logos;
 
+use crate::error::{Span, YelraError};
+
 #[derive(Logos, Debug, Clone, PartialEq)]
 pub enum Token {
     #[token("(")]
@@ -9,30 +11,140 @@ pub enum Token {
     #[token(")")]
     RParen,
 
+    // Accepted as `(`/`)` when `LexerOptions::allow_square_brackets` is set.
+    #[token("[")]
+    LBracket,
+    #[token("]")]
+    RBracket,
+
     // Numbers (priority beats Symbol)
     #[regex(r"-?[0-9]+(\.[0-9]+)?", |lex| lex.slice().to_string(), priority = 3)]
     Number(String),
 
-    // Operators and Identifiers (merged into one Symbol variant)
-    #[regex(r"[+\-*/=<>!]+|[A-Za-z_][A-Za-z0-9_]*", |lex| lex.slice().to_string(), priority = 2)]
+    // Double-quoted string literal, with `\"`/`\\` escapes handled so the
+    // closing quote isn't mistaken for the end of the string.
+    #[regex(r#""([^"\\]|\\.)*""#, |lex| lex.slice().to_string())]
+    Str(String),
+
+    // Single-quoted string literal, accepted as an alternate string
+    // delimiter when `LexerOptions::allow_single_quote_strings` is set.
+    #[regex(r"'([^'\\]|\\.)*'", |lex| lex.slice().to_string())]
+    SingleQuoteStr(String),
+
+    // `;`-to-end-of-line comment, skipped when
+    // `LexerOptions::skip_line_comments` is set.
+    #[regex(r";[^\n]*", |lex| lex.slice().to_string())]
+    Comment(String),
+
+    // Operators and Identifiers (merged into one Symbol variant). Identifiers
+    // allow interior `-` (e.g. `string-append`) so kebab-case Scheme-style
+    // names are usable — the operator alternative only matches runs made
+    // entirely of operator characters, so a leading `-` still lexes as `-`.
+    // `^` is included so the right-associative exponent operator (see
+    // `binding_power` in parser.rs) actually lexes.
+    #[regex(r"[+\-*/=<>!^]+|[A-Za-z_][A-Za-z0-9_-]*", |lex| lex.slice().to_string(), priority = 2)]
     Symbol(String),
 
+    // A backslash-quoted operator, e.g. `\+` — boxes the operator as a
+    // first-class value instead of it only being meaningful in head
+    // position of a list.
+    #[regex(r"\\[+\-*/=<>!^]+", |lex| lex.slice().to_string())]
+    Quoted(String),
+
     // Skip whitespace
     #[regex(r"[ \t\r\n]+", logos::skip)]
     Whitespace,
 }
 
-pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+/// Configurable lexer syntax, following the configurable-reader design of
+/// the `lexpr` crate: which optional surface syntax `tokenize` accepts.
+#[derive(Debug, Clone)]
+pub struct LexerOptions {
+    /// Accept `'...'` as an alternate string literal delimiter alongside `"..."`.
+    pub allow_single_quote_strings: bool,
+    /// Accept `[`/`]` as equivalent to `(`/`)`.
+    pub allow_square_brackets: bool,
+    /// Skip `;`-to-end-of-line comments instead of erroring on `;`.
+    pub skip_line_comments: bool,
+}
+
+impl Default for LexerOptions {
+    fn default() -> Self {
+        LexerOptions {
+            allow_single_quote_strings: true,
+            allow_square_brackets: true,
+            skip_line_comments: true,
+        }
+    }
+}
+
+/// Unescape the body of a string literal (the slice between its delimiter
+/// quotes), turning `\n`, `\t`, `\"`, `\'`, and `\\` into their literal
+/// characters.
+fn unescape(body: &str, span: &Span) -> Result<String, YelraError> {
+    let mut out = String::with_capacity(body.len());
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('"') => out.push('"'),
+            Some('\'') => out.push('\''),
+            Some('\\') => out.push('\\'),
+            _ => return Err(YelraError::InvalidEscape { span: span.clone() }),
+        }
+    }
+    Ok(out)
+}
+
+pub fn tokenize(input: &str, options: &LexerOptions) -> Result<Vec<(Token, Span)>, YelraError> {
     let mut lexer = Token::lexer(input);
     let mut tokens = Vec::new();
 
     while let Some(res) = lexer.next() {
         match res {
             Ok(Token::Whitespace) => continue,
-            Ok(tok) => tokens.push(tok),
-            Err(_) => {
+            Ok(Token::Comment(_)) => {
+                if options.skip_line_comments {
+                    continue;
+                }
+                return Err(YelraError::UnexpectedChar { span: lexer.span() });
+            }
+            Ok(tok @ (Token::LBracket | Token::RBracket)) => {
+                if !options.allow_square_brackets {
+                    return Err(YelraError::UnexpectedChar { span: lexer.span() });
+                }
+                let replacement = if matches!(tok, Token::LBracket) {
+                    Token::LParen
+                } else {
+                    Token::RParen
+                };
+                tokens.push((replacement, lexer.span()));
+            }
+            Ok(Token::Str(raw)) => {
                 let span = lexer.span();
-                return Err(format!("Unexpected token at {}..{}", span.start, span.end));
+                let body = &raw[1..raw.len() - 1];
+                tokens.push((Token::Str(unescape(body, &span)?), span));
+            }
+            Ok(Token::SingleQuoteStr(raw)) => {
+                let span = lexer.span();
+                if !options.allow_single_quote_strings {
+                    return Err(YelraError::UnexpectedChar { span });
+                }
+                let body = &raw[1..raw.len() - 1];
+                tokens.push((Token::Str(unescape(body, &span)?), span));
+            }
+            Ok(Token::Quoted(raw)) => {
+                let span = lexer.span();
+                tokens.push((Token::Quoted(raw[1..].to_string()), span));
+            }
+            Ok(tok) => tokens.push((tok, lexer.span())),
+            Err(_) => {
+                return Err(YelraError::UnexpectedChar { span: lexer.span() });
             }
         }
     }
@@ -1,39 +1,122 @@
 // src/main.rs
 
 mod ast;
+mod error;
+mod eval;
 mod lexer;
 mod parser;
 
-use lexer::tokenize;
+use error::{Span, YelraError};
+use eval::Env;
+use lexer::{tokenize, LexerOptions, Token};
 use parser::parse;
-use std::io::{self, Write};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+const HISTORY_FILE: &str = "history.txt";
 
 fn main() {
     println!("yelra v0.1 — type 'exit' or Ctrl+D to quit");
 
-    let stdin = io::stdin();
+    let mut env = Env::new();
+    let lexer_options = LexerOptions::default();
+
+    let mut rl = DefaultEditor::new().expect("failed to initialize line editor");
+    let _ = rl.load_history(HISTORY_FILE);
+
     loop {
-        print!("> ");
-        io::stdout().flush().unwrap();
+        let input = match read_expression(&mut rl, &lexer_options) {
+            Ok(Some(input)) => input,
+            Ok(None) => break, // Ctrl+D
+            Err(e) => {
+                eprintln!("Readline error: {}", e);
+                break;
+            }
+        };
 
-        let mut input = String::new();
-        if stdin.read_line(&mut input).unwrap() == 0 {
-            break; // EOF
-        }
         let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
+        let _ = rl.add_history_entry(input);
         if input == "exit" {
             break;
         }
 
-        match tokenize(input) {
+        match tokenize(input, &lexer_options) {
             Ok(tokens) => match parse(tokens) {
-                Ok(expr) => match ast::eval(&expr) {
+                Ok(expr) => match eval::eval(&expr, &mut env) {
                     Ok(val) => println!("{}", val),
-                    Err(e) => println!("Eval error: {}", e),
+                    Err(e) => report_error(input, &e),
                 },
-                Err(e) => println!("Parse error: {}", e),
+                Err(e) => report_error(input, &e),
             },
-            Err(e) => println!("Lex error: {}", e),
+            Err(e) => report_error(input, &e),
+        }
+    }
+
+    let _ = rl.save_history(HISTORY_FILE);
+}
+
+/// Read one full expression, transparently continuing onto further lines
+/// (with a `...` prompt) while the parens opened so far aren't balanced.
+/// Returns `Ok(None)` on Ctrl+D with nothing entered yet.
+fn read_expression(
+    rl: &mut DefaultEditor,
+    options: &LexerOptions,
+) -> Result<Option<String>, ReadlineError> {
+    let mut buffer = match rl.readline("> ") {
+        Ok(line) => line,
+        Err(ReadlineError::Eof) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    while needs_continuation(&buffer, options) {
+        match rl.readline("... ") {
+            Ok(line) => {
+                buffer.push('\n');
+                buffer.push_str(&line);
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e),
         }
     }
+
+    Ok(Some(buffer))
+}
+
+/// Whether `input` has more `(` than `)` so far, meaning it's an
+/// incomplete expression that should keep reading further lines. A lex
+/// error is left for the normal error-reporting path rather than treated
+/// as a reason to continue.
+fn needs_continuation(input: &str, options: &LexerOptions) -> bool {
+    match tokenize(input, options) {
+        Ok(tokens) => paren_depth(&tokens) > 0,
+        Err(_) => false,
+    }
+}
+
+fn paren_depth(tokens: &[(Token, Span)]) -> i64 {
+    tokens.iter().fold(0i64, |depth, (tok, _)| match tok {
+        Token::LParen => depth + 1,
+        Token::RParen => depth - 1,
+        _ => depth,
+    })
+}
+
+/// Print an error message and, if it carries a source span, a
+/// caret-underlined snippet pointing at the offending region of `input`.
+fn report_error(input: &str, err: &YelraError) {
+    println!("Error: {}", err);
+    if let Some(span) = err.span() {
+        let start = span.start.min(input.len());
+        let end = span.end.clamp(start + 1, input.len().max(start + 1));
+        // `span` is a byte range, but the caret line is printed in terminal
+        // columns — convert to char counts so multibyte UTF-8 before the
+        // span doesn't shift the underline out of alignment.
+        let col = input[..start].chars().count();
+        let width = input[start..end].chars().count();
+        println!("{}", input);
+        println!("{}{}", " ".repeat(col), "^".repeat(width));
+    }
 }
@@ -1,28 +1,50 @@
 // parser.rs
 
 use crate::ast::Expr;
+use crate::error::{Span, YelraError};
 use crate::lexer::Token;
 
+/// Returns the (left, right) binding power of an infix operator, or `None`
+/// if `op` is not a known infix operator.
+///
+/// Left-associative operators have `left_bp < right_bp`; right-associative
+/// operators (like `^`) flip that so recursing on the rhs binds tighter,
+/// pulling further occurrences of the same operator into the right branch.
+fn binding_power(op: &str) -> Option<(u8, u8)> {
+    match op {
+        "+" | "-" => Some((1, 2)),
+        "*" | "/" => Some((3, 4)),
+        "^" => Some((6, 5)),
+        _ => None,
+    }
+}
+
 pub struct Parser {
-    tokens: Vec<Token>,
+    tokens: Vec<(Token, Span)>,
     pos: usize,
+    /// Byte offset just past the last token, used as the anchor span when
+    /// an error has nothing left to point at (end of input).
+    eof: usize,
 }
 
 impl Parser {
-    pub fn new(tokens: Vec<Token>) -> Self {
-        let tokens: Vec<Token> = tokens
-            .into_iter()
-            .filter(|t| !matches!(t, Token::Whitespace))
-            .collect();
-
-        Parser { tokens, pos: 0 }
+    pub fn new(tokens: Vec<(Token, Span)>) -> Self {
+        let eof = tokens.last().map(|(_, s)| s.end).unwrap_or(0);
+        Parser { tokens, pos: 0, eof }
     }
 
     fn peek(&self) -> Option<&Token> {
-        self.tokens.get(self.pos)
+        self.tokens.get(self.pos).map(|(t, _)| t)
     }
 
-    fn next(&mut self) -> Option<Token> {
+    fn peek_span(&self) -> Span {
+        self.tokens
+            .get(self.pos)
+            .map(|(_, s)| s.clone())
+            .unwrap_or(self.eof..self.eof)
+    }
+
+    fn next(&mut self) -> Option<(Token, Span)> {
         if self.pos < self.tokens.len() {
             let t = self.tokens[self.pos].clone();
             self.pos += 1;
@@ -32,20 +54,23 @@ impl Parser {
         }
     }
 
-    pub fn parse_expr(&mut self) -> Result<Expr, String> {
-        // Parse a primary expression first
-        let first = match self.next() {
-            Some(Token::Number(s)) => {
+    /// Parse a primary expression: a number, a bare symbol, or a
+    /// parenthesized list.
+    fn parse_primary(&mut self) -> Result<Expr, YelraError> {
+        match self.next() {
+            Some((Token::Number(s), span)) => {
                 let n: f64 = s
                     .parse()
-                    .map_err(|e| format!("Invalid number '{}': {}", s, e))?;
-                Expr::Number(n)
+                    .map_err(|_| YelraError::MalformedNumber { span })?;
+                Ok(Expr::Number(n))
             }
-            Some(Token::Symbol(s)) => {
+            Some((Token::Symbol(s), _)) => {
                 // A bare symbol (not inside parentheses) — return as symbol
-                Expr::Symbol(s)
+                Ok(Expr::Symbol(s))
             }
-            Some(Token::LParen) => {
+            Some((Token::Str(s), _)) => Ok(Expr::Str(s)),
+            Some((Token::Quoted(op), _)) => Ok(Expr::Quote(op)),
+            Some((Token::LParen, open_span)) => {
                 // parse list until matching RParen
                 let mut exprs = Vec::new();
                 while let Some(tok) = self.peek() {
@@ -58,89 +83,110 @@ impl Parser {
                         exprs.push(e);
                     }
                 }
-                return Err("Unclosed '(' — reached end of input".to_string());
+                Err(YelraError::UnclosedParen { span: open_span })
             }
-            Some(Token::RParen) => return Err("Unexpected ')'".to_string()),
-            Some(Token::Whitespace) => return Err("Unexpected whitespace token".to_string()),
-            None => return Err("Unexpected end of input".to_string()),
-        };
-
-        // If the primary expression is a Number or a List, try to parse infix continuation:
-        // pattern: first (Symbol op, Expr rhs)+
-        match &first {
-            Expr::Number(_) | Expr::List(_) => {
-                let mut operands: Vec<Expr> = vec![first.clone()];
-                let mut ops: Vec<String> = Vec::new();
-
-                // collect (op, rhs) pairs greedily, but only when the next token is a Symbol
-                loop {
-                    match self.peek() {
-                        Some(Token::Symbol(_)) => {
-                            // consume operator symbol
-                            let op = match self.next() {
-                                Some(Token::Symbol(s)) => s,
-                                _ => unreachable!(),
-                            };
-                            // parse rhs expression
-                            let rhs = self.parse_expr()?;
-                            ops.push(op);
-                            operands.push(rhs);
-                        }
-                        _ => break,
-                    }
-                }
+            Some((Token::RParen, span)) => Err(YelraError::UnexpectedRParen { span }),
+            // `LBracket`/`RBracket`/`SingleQuoteStr`/`Comment`/`Whitespace` are
+            // always normalized or filtered out by `tokenize` before the
+            // parser ever sees them.
+            Some((_, span)) => Err(YelraError::UnexpectedChar { span }),
+            None => Err(YelraError::UnexpectedEof {
+                span: self.eof..self.eof,
+            }),
+        }
+    }
 
-                if ops.is_empty() {
-                    // no infix continuation; just return the primary expr
-                    Ok(operands.into_iter().next().unwrap())
-                } else if ops.len() == 1 {
-                    // single operator: (op first rhs)
-                    let op = ops[0].clone();
-                    let mut list: Vec<Expr> = Vec::new();
-                    list.push(Expr::Symbol(op));
-                    list.extend(operands.into_iter());
-                    Ok(Expr::List(list))
-                } else {
-                    // multiple operators: ensure they are all the same (left-assoc, same-op only)
-                    let all_same = ops.iter().all(|o| o == &ops[0]);
-                    if all_same {
-                        let op0 = ops[0].clone();
-                        let mut list: Vec<Expr> = Vec::new();
-                        list.push(Expr::Symbol(op0));
-                        list.extend(operands.into_iter());
-                        Ok(Expr::List(list))
-                    } else {
-                        Err("Mixed operators without parentheses are not supported — use parentheses to disambiguate.".to_string())
-                    }
-                }
+    /// Precedence-climbing (Pratt) parse: a primary, followed by zero or
+    /// more infix operators whose left binding power is at least `min_bp`.
+    /// Lower-precedence operators are left for an outer call to pick up,
+    /// which is what makes `1 + 2 * 3` group the multiplication first.
+    fn parse_bp(&mut self, min_bp: u8) -> Result<Expr, YelraError> {
+        let mut lhs = self.parse_primary()?;
+
+        // A bare symbol isn't a valid infix operand (e.g. the user typed
+        // "+ 1 2" without parentheses) — return it as-is, matching the
+        // previous behaviour.
+        if matches!(lhs, Expr::Symbol(_)) {
+            return Ok(lhs);
+        }
+
+        while let Some(Token::Symbol(s)) = self.peek() {
+            let op = s.clone();
+
+            let (left_bp, right_bp) = match binding_power(&op) {
+                Some(bp) => bp,
+                None => break,
+            };
+            if left_bp < min_bp {
+                break;
             }
-            // If the first expression is a Symbol (e.g. user typed "+ 1 2" without parentheses),
-            // don't attempt to treat following tokens as infix; just return the symbol expression.
-            Expr::Symbol(_) => Ok(first),
+
+            self.next(); // consume the operator
+            let rhs = self.parse_bp(right_bp)?;
+            lhs = Expr::List(vec![Expr::Symbol(op), lhs, rhs]);
         }
+
+        Ok(lhs)
+    }
+
+    pub fn parse_expr(&mut self) -> Result<Expr, YelraError> {
+        self.parse_bp(0)
     }
 }
 
 // top-level parse entry
-pub fn parse(tokens: Vec<Token>) -> Result<Expr, String> {
+pub fn parse(tokens: Vec<(Token, Span)>) -> Result<Expr, YelraError> {
     let mut p = Parser::new(tokens);
     let expr = p.parse_expr()?;
 
     // Ensure we've consumed all tokens; if anything remains, report where we stopped.
-    if let Some(remaining) = p.peek() {
-        // Give a clearer message for debugging leftover tokens
-        let kind = match remaining {
-            Token::LParen => "('(')".to_string(),
-            Token::RParen => "')'".to_string(),
-            Token::Number(n) => format!("number `{}`", n),
-            Token::Symbol(s) => format!("symbol `{}`", s),
-            Token::Whitespace => "whitespace".to_string(),
-        };
-        return Err(format!(
-            "Extra tokens after first expression (next token: {})",
-            kind
-        ));
+    if p.peek().is_some() {
+        return Err(YelraError::TrailingTokens {
+            span: p.peek_span(),
+        });
     }
 
     Ok(expr)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::{tokenize, LexerOptions};
+
+    fn parse_str(input: &str) -> Expr {
+        let tokens = tokenize(input, &LexerOptions::default()).unwrap();
+        parse(tokens).unwrap()
+    }
+
+    fn sym(s: &str) -> Expr {
+        Expr::Symbol(s.to_string())
+    }
+
+    #[test]
+    fn precedence_groups_multiplication_tighter_than_addition() {
+        // "1 + 2 * 3 - 4" should group as ((1 + (2 * 3)) - 4), i.e. `*`
+        // binds tighter than the left-associative `+`/`-` chain.
+        let expected = Expr::List(vec![
+            sym("-"),
+            Expr::List(vec![
+                sym("+"),
+                Expr::Number(1.0),
+                Expr::List(vec![sym("*"), Expr::Number(2.0), Expr::Number(3.0)]),
+            ]),
+            Expr::Number(4.0),
+        ]);
+        assert_eq!(parse_str("1 + 2 * 3 - 4"), expected);
+    }
+
+    #[test]
+    fn caret_is_right_associative() {
+        // "2 ^ 3 ^ 2" should group as (2 ^ (3 ^ 2)), not ((2 ^ 3) ^ 2).
+        let expected = Expr::List(vec![
+            sym("^"),
+            Expr::Number(2.0),
+            Expr::List(vec![sym("^"), Expr::Number(3.0), Expr::Number(2.0)]),
+        ]);
+        assert_eq!(parse_str("2 ^ 3 ^ 2"), expected);
+    }
+}